@@ -1,67 +1,185 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::fs::File;
+use std::io;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 
-pub fn ptr_info<T>(ptr: *const T) {
-    let address = ptr as usize;
-    println!("Pointer address in hex: {:p}", ptr);
+/// The four permission bits of a `/proc/[pid]/maps` entry (`rwxp`/`rwxs`).
+pub struct Permissions {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+    pub shared: bool,
+}
 
-    let maps_path = Path::new("/proc/self/maps");
-    let file = match File::open(maps_path) {
-        Ok(file) => file,
-        Err(e) => {
-            eprintln!("Failed to open {:?}: {}", maps_path, e);
-            return;
+impl Permissions {
+    fn parse(field: &str) -> Option<Permissions> {
+        let mut chars = field.chars();
+        let read = chars.next()? == 'r';
+        let write = chars.next()? == 'w';
+        let execute = chars.next()? == 'x';
+        let shared = match chars.next()? {
+            's' => true,
+            'p' => false,
+            _ => return None,
+        };
+        Some(Permissions {
+            read,
+            write,
+            execute,
+            shared,
+        })
+    }
+
+    /// The inverse of [`Permissions::shared`]: `true` for copy-on-write (`p`) mappings.
+    pub fn private(&self) -> bool {
+        !self.shared
+    }
+}
+
+impl fmt::Debug for Permissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}",
+            if self.read { 'r' } else { '-' },
+            if self.write { 'w' } else { '-' },
+            if self.execute { 'x' } else { '-' },
+            if self.shared { 's' } else { 'p' },
+        )
+    }
+}
+
+/// An error parsing or reading a `/proc/[pid]/maps` file.
+#[derive(Debug)]
+pub enum PtrInfoError {
+    /// The maps file could not be opened or read.
+    Io(io::Error),
+    /// A line of the maps file did not have the expected format.
+    Parse { line: String },
+}
+
+impl fmt::Display for PtrInfoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PtrInfoError::Io(e) => write!(f, "failed to read maps file: {}", e),
+            PtrInfoError::Parse { line } => write!(f, "could not parse maps line: {:?}", line),
         }
-    };
+    }
+}
+
+impl std::error::Error for PtrInfoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PtrInfoError::Io(e) => Some(e),
+            PtrInfoError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for PtrInfoError {
+    fn from(e: io::Error) -> Self {
+        PtrInfoError::Io(e)
+    }
+}
+
+/// A single mapping parsed out of a `/proc/[pid]/maps` file.
+#[derive(Debug)]
+pub struct MemoryRegion {
+    pub start_address: usize,
+    pub end_address: usize,
+    pub permissions: Permissions,
+    pub offset: usize,
+    pub dev_major: u32,
+    pub dev_minor: u32,
+    pub inode: u64,
+    pub pathname: Option<String>,
+}
 
+/// Looks up the memory region of the current process that contains `ptr`.
+///
+/// Returns `Ok(None)` if `ptr` does not fall inside any mapping listed in
+/// `/proc/self/maps`, and an `Err` if the file could not be read or a line
+/// of it was malformed.
+pub fn region_of<T>(ptr: *const T) -> Result<Option<MemoryRegion>, PtrInfoError> {
+    find_region(Path::new("/proc/self/maps"), ptr as usize)
+}
+
+/// Looks up the memory region of process `pid` that contains `address`.
+///
+/// Unlike [`region_of`], this inspects another process, so there is no live
+/// `*const T` to take the address from; callers pass the raw `usize` instead.
+/// Returns `Ok(None)` if `address` does not fall inside any mapping, and an
+/// `Err` if `/proc/{pid}/maps` could not be read (e.g. `ENOENT` for a dead
+/// pid, `EACCES` for a denied process) or contained a malformed line —
+/// including a line unrelated to `address`, since a line we can't parse
+/// means we can't trust the rest of the file either.
+pub fn region_in_process(pid: u32, address: usize) -> Result<Option<MemoryRegion>, PtrInfoError> {
+    find_region(Path::new(&format!("/proc/{}/maps", pid)), address)
+}
+
+fn find_region(maps_path: &Path, address: usize) -> Result<Option<MemoryRegion>, PtrInfoError> {
+    let file = File::open(maps_path)?;
     let reader = BufReader::new(file);
-    let mut file_counts = HashMap::<String, i32>::new();
-    let mut lines = Vec::new();
 
+    let mut found = None;
     for line in reader.lines() {
-        let line = line.unwrap_or_else(|_| String::new());
-        lines.push(line.clone());
-
-        if let Some((_, _, _, Some(file_path))) = parse_line(&line) {
-            // Clone `file_path` here to store an owned String in the HashMap
-            let file_path_owned = file_path.clone();
-            *file_counts.entry(file_path_owned).or_insert(0) += 1;
+        let region = parse_line(&line?)?;
+        if found.is_none() && address >= region.start_address && address < region.end_address {
+            found = Some(region);
         }
     }
 
-    for line in &lines {
-        if let Some((start, end, permissions, file_path_option)) = parse_line(line) {
-            if address >= start && address <= end {
-                let output = match &file_path_option {
-                    Some(file_path) => {
-                        // Use &file_path to borrow the String
-                        let count = file_counts.get(file_path).unwrap_or(&1);
-                        if *count > 1 {
-                            format!("{} [{}]", file_path, count)
-                        } else {
-                            file_path.to_string()
-                        }
-                    }
-                    None => "anonymous".to_string(),
-                };
-                println!(
-                    "The pointer is in the {} section, permissions: {}, associated file: {}",
-                    // Use .as_deref() to convert Option<String> to Option<&str>
-                    determine_region_type(permissions, file_path_option.as_deref()),
-                    permissions,
-                    output
-                );
-                return;
-            }
+    Ok(found)
+}
+
+/// Parses every mapping of the current process into a [`MemoryRegion`], in
+/// the order `/proc/self/maps` lists them.
+///
+/// This is the general-purpose entry point for VM inspection: callers can
+/// sum region sizes, count distinct backing files, or locate the stack/heap
+/// extents from the returned vector.
+pub fn memory_map() -> Result<Vec<MemoryRegion>, PtrInfoError> {
+    read_regions(Path::new("/proc/self/maps"))
+}
+
+/// Parses every mapping of process `pid` into a [`MemoryRegion`], in the
+/// order `/proc/{pid}/maps` lists them.
+pub fn memory_map_in_process(pid: u32) -> Result<Vec<MemoryRegion>, PtrInfoError> {
+    read_regions(Path::new(&format!("/proc/{}/maps", pid)))
+}
+
+fn read_regions(maps_path: &Path) -> Result<Vec<MemoryRegion>, PtrInfoError> {
+    let file = File::open(maps_path)?;
+    let reader = BufReader::new(file);
+
+    let mut regions = Vec::new();
+    for line in reader.lines() {
+        regions.push(parse_line(&line?)?);
+    }
+    Ok(regions)
+}
+
+/// Counts how many regions in `regions` share each pathname, keyed by the
+/// pathname. Anonymous regions (no pathname) are not counted.
+fn path_occurrences(regions: &[MemoryRegion]) -> HashMap<&str, i32> {
+    let mut counts = HashMap::new();
+    for region in regions {
+        if let Some(path) = region.pathname.as_deref() {
+            *counts.entry(path).or_insert(0) += 1;
         }
     }
+    counts
+}
 
-    println!("The pointer address does not belong to any known section.");
+fn parse_line(line: &str) -> Result<MemoryRegion, PtrInfoError> {
+    parse_line_opt(line).ok_or_else(|| PtrInfoError::Parse {
+        line: line.to_string(),
+    })
 }
 
-fn parse_line(line: &str) -> Option<(usize, usize, &str, Option<String>)> {
+fn parse_line_opt(line: &str) -> Option<MemoryRegion> {
     let parts: Vec<&str> = line.split_whitespace().collect();
     if parts.len() < 5 {
         return None;
@@ -72,24 +190,115 @@ fn parse_line(line: &str) -> Option<(usize, usize, &str, Option<String>)> {
         return None;
     }
 
-    let start = usize::from_str_radix(range[0], 16).ok()?;
-    let end = usize::from_str_radix(range[1], 16).ok()?;
-    let permissions = parts[1];
-    // Change `file_path` to return an owned String instead of a borrowed &str
-    let file_path = parts.get(5).map(|&s| s.to_owned());
+    let start_address = usize::from_str_radix(range[0], 16).ok()?;
+    let end_address = usize::from_str_radix(range[1], 16).ok()?;
+    let permissions = Permissions::parse(parts[1])?;
+    let offset = usize::from_str_radix(parts[2], 16).ok()?;
+
+    let dev: Vec<&str> = parts[3].split(':').collect();
+    if dev.len() != 2 {
+        return None;
+    }
+    let dev_major = u32::from_str_radix(dev[0], 16).ok()?;
+    let dev_minor = u32::from_str_radix(dev[1], 16).ok()?;
+
+    let inode = parts[4].parse::<u64>().ok()?;
+
+    // The pathname can itself contain spaces, so join everything past column
+    // five instead of indexing a single element.
+    let pathname = if parts.len() > 5 {
+        Some(parts[5..].join(" "))
+    } else {
+        None
+    };
+
+    Some(MemoryRegion {
+        start_address,
+        end_address,
+        permissions,
+        offset,
+        dev_major,
+        dev_minor,
+        inode,
+        pathname,
+    })
+}
+
+/// Prints a human-readable description of the region containing `ptr`.
+///
+/// This is a thin formatter over [`memory_map`]; use that function (or
+/// [`region_of`]) directly if you need the structured data instead of a
+/// printed sentence.
+pub fn ptr_info<T>(ptr: *const T) {
+    println!("Pointer address in hex: {:p}", ptr);
+
+    let address = ptr as usize;
+    let regions = match memory_map() {
+        Ok(regions) => regions,
+        Err(e) => {
+            eprintln!("{}", e);
+            return;
+        }
+    };
+
+    let region = regions
+        .iter()
+        .find(|region| address >= region.start_address && address < region.end_address);
 
-    Some((start, end, permissions, file_path))
+    match region {
+        Some(region) => {
+            let output = match &region.pathname {
+                Some(path) => {
+                    let count = path_occurrences(&regions)
+                        .get(path.as_str())
+                        .copied()
+                        .unwrap_or(1);
+                    if count > 1 {
+                        format!("{} [{}]", path, count)
+                    } else {
+                        path.clone()
+                    }
+                }
+                None => "anonymous".to_string(),
+            };
+            println!(
+                "The pointer is in the {} section, permissions: {:?}, associated file: {}",
+                region.region_type(),
+                region.permissions,
+                output
+            );
+        }
+        None => println!("The pointer address does not belong to any known section."),
+    }
 }
 
-fn determine_region_type(permissions: &str, file_path: Option<&str>) -> &'static str {
-    match (permissions, file_path) {
-        (_, Some(path)) if path.contains("[stack]") => "stack",
-        (_, Some(path)) if path.contains("[heap]") => "heap",
-        ("r-xp", _) => "text (executable code)",
-        ("rw-p", Some(path)) if path.contains(".so") => "data in shared library",
-        ("r--p", Some(path)) if path.contains(".so") => "read-only data in shared library",
-        ("rw-p", _) => "data or BSS",
-        _ => "other",
+impl MemoryRegion {
+    /// Classifies the region based on its permissions and pathname, e.g.
+    /// `"stack"`, `"heap"`, `"code in shared library"`, or `"guard page"`.
+    pub fn region_type(&self) -> &'static str {
+        let permissions = &self.permissions;
+        let path = self.pathname.as_deref();
+
+        let is_rxp = permissions.read && !permissions.write && permissions.execute;
+        let is_rwp = permissions.read && permissions.write && !permissions.execute;
+        let is_r_p = permissions.read && !permissions.write && !permissions.execute;
+        let is_guard_page = !permissions.read && !permissions.write && !permissions.execute;
+
+        match path {
+            Some(path) if path.contains("[stack:") => "thread stack",
+            Some(path) if path.contains("[stack]") => "stack",
+            Some(path) if path.contains("[heap]") => "heap",
+            Some(path) if path.contains("[vdso]") => "vdso",
+            Some(path) if path.contains("[vvar]") => "vvar",
+            Some(path) if path.contains("[vsyscall]") => "vsyscall",
+            _ if is_guard_page => "guard page",
+            Some(path) if is_rxp && path.contains(".so") => "code in shared library",
+            _ if is_rxp => "text (executable code)",
+            Some(path) if is_rwp && path.contains(".so") => "data in shared library",
+            Some(path) if is_r_p && path.contains(".so") => "read-only data in shared library",
+            _ if is_rwp => "data or BSS",
+            _ => "other",
+        }
     }
 }
 
@@ -100,65 +309,158 @@ mod tests {
     #[test]
     fn test_parse_line_valid() {
         let line = "00400000-0040c000 r-xp 00000000 fc:01 123456 /usr/bin/cat";
-        let parsed = parse_line(line).unwrap();
-        assert_eq!(parsed.0, 0x400000); // start address
-        assert_eq!(parsed.1, 0x40c000); // end address
-        assert_eq!(parsed.2, "r-xp"); // permissions
-        assert_eq!(parsed.3, Some("/usr/bin/cat".to_string())); // file path
+        let region = parse_line(line).unwrap();
+        assert_eq!(region.start_address, 0x400000);
+        assert_eq!(region.end_address, 0x40c000);
+        assert!(region.permissions.read);
+        assert!(!region.permissions.write);
+        assert!(region.permissions.execute);
+        assert!(region.permissions.private());
+        assert_eq!(region.dev_major, 0xfc);
+        assert_eq!(region.dev_minor, 0x01);
+        assert_eq!(region.inode, 123456);
+        assert_eq!(region.pathname, Some("/usr/bin/cat".to_string()));
+    }
+
+    #[test]
+    fn test_parse_line_pathname_with_spaces() {
+        let line = "00400000-0040c000 r-xp 00000000 fc:01 123456 /usr/bin/my app";
+        let region = parse_line(line).unwrap();
+        assert_eq!(region.pathname, Some("/usr/bin/my app".to_string()));
+    }
+
+    #[test]
+    fn test_parse_line_anonymous() {
+        let line = "7f000000-7f001000 rw-p 00000000 00:00 0";
+        let region = parse_line(line).unwrap();
+        assert_eq!(region.pathname, None);
     }
 
     #[test]
     fn test_parse_line_invalid_format() {
         let line = "invalid format line";
-        assert!(parse_line(line).is_none());
+        match parse_line(line) {
+            Err(PtrInfoError::Parse { line: bad }) => assert_eq!(bad, line),
+            other => panic!("expected Parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_permissions_debug_shared() {
+        let permissions = Permissions::parse("rw-s").unwrap();
+        assert_eq!(format!("{:?}", permissions), "rw-s");
+        assert!(!permissions.private());
+    }
+
+    #[test]
+    fn test_ptr_info_error_display() {
+        let err = PtrInfoError::Parse {
+            line: "garbage".to_string(),
+        };
+        assert!(format!("{}", err).contains("garbage"));
+    }
+
+    fn region_with(permissions: &str, path: &str) -> MemoryRegion {
+        parse_line(&format!(
+            "00400000-0040c000 {} 00000000 fc:01 1 {}",
+            permissions, path
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_region_type_stack() {
+        assert_eq!(region_with("rw-p", "[stack]").region_type(), "stack");
+    }
+
+    #[test]
+    fn test_region_type_thread_stack() {
+        assert_eq!(
+            region_with("rw-p", "[stack:1234]").region_type(),
+            "thread stack"
+        );
+    }
+
+    #[test]
+    fn test_region_type_heap() {
+        assert_eq!(region_with("rw-p", "[heap]").region_type(), "heap");
+    }
+
+    #[test]
+    fn test_region_type_vdso_vvar_vsyscall() {
+        assert_eq!(region_with("r-xp", "[vdso]").region_type(), "vdso");
+        assert_eq!(region_with("r--p", "[vvar]").region_type(), "vvar");
+        assert_eq!(region_with("r-xp", "[vsyscall]").region_type(), "vsyscall");
     }
 
     #[test]
-    fn test_determine_region_type_stack() {
-        let permissions = "rw-p";
-        let file_path = Some("[stack]");
-        assert_eq!(determine_region_type(permissions, file_path), "stack");
+    fn test_region_type_guard_page() {
+        let region = parse_line("00400000-0040c000 ---p 00000000 fc:01 1").unwrap();
+        assert_eq!(region.region_type(), "guard page");
     }
 
     #[test]
-    fn test_determine_region_type_heap() {
-        let permissions = "rw-p";
-        let file_path = Some("[heap]");
-        assert_eq!(determine_region_type(permissions, file_path), "heap");
+    fn test_region_type_text_executable_code() {
+        let region = parse_line("00400000-0040c000 r-xp 00000000 fc:01 1").unwrap();
+        assert_eq!(region.region_type(), "text (executable code)");
     }
 
     #[test]
-    fn test_determine_region_type_text_executable_code() {
-        let permissions = "r-xp";
+    fn test_region_type_code_in_shared_library() {
         assert_eq!(
-            determine_region_type(permissions, None),
-            "text (executable code)"
+            region_with("r-xp", "/lib/libexample.so").region_type(),
+            "code in shared library"
         );
     }
 
     #[test]
-    fn test_determine_region_type_data_in_shared_library() {
-        let permissions = "rw-p";
-        let _file_path = Some("libexample.so");
+    fn test_region_type_data_in_shared_library() {
         assert_eq!(
-            determine_region_type(permissions, Some("libexample.so")),
+            region_with("rw-p", "/lib/libexample.so").region_type(),
             "data in shared library"
         );
     }
 
     #[test]
-    fn test_determine_region_type_read_only_data_in_shared_library() {
-        let permissions = "r--p";
-        let _file_path = Some("libreadonlydata.so");
+    fn test_region_type_read_only_data_in_shared_library() {
         assert_eq!(
-            determine_region_type(permissions, Some("libreadonlydata.so")),
+            region_with("r--p", "/lib/libreadonlydata.so").region_type(),
             "read-only data in shared library"
         );
     }
 
     #[test]
-    fn test_determine_region_type_data_or_bss() {
-        let permissions = "rw-p";
-        assert_eq!(determine_region_type(permissions, None), "data or BSS");
+    fn test_region_in_process_nonexistent_pid_is_err() {
+        // PID 0 is never a real process, so its maps file can't exist.
+        assert!(region_in_process(0, 0).is_err());
+    }
+
+    #[test]
+    fn test_memory_map_in_process_nonexistent_pid_is_err() {
+        assert!(memory_map_in_process(0).is_err());
+    }
+
+    #[test]
+    fn test_memory_map_contains_current_process_regions() {
+        let regions = memory_map().unwrap();
+        assert!(!regions.is_empty());
+    }
+
+    #[test]
+    fn test_path_occurrences_counts_shared_paths() {
+        let regions = vec![
+            parse_line("00400000-0040c000 r-xp 00000000 fc:01 1 /lib/libc.so").unwrap(),
+            parse_line("0040c000-0040d000 r--p 0000c000 fc:01 1 /lib/libc.so").unwrap(),
+            parse_line("7f000000-7f001000 rw-p 00000000 00:00 0").unwrap(),
+        ];
+        let counts = path_occurrences(&regions);
+        assert_eq!(counts.get("/lib/libc.so"), Some(&2));
+        assert_eq!(counts.len(), 1);
+    }
+
+    #[test]
+    fn test_region_type_data_or_bss() {
+        let region = parse_line("00400000-0040c000 rw-p 00000000 fc:01 1").unwrap();
+        assert_eq!(region.region_type(), "data or BSS");
     }
 }